@@ -0,0 +1,122 @@
+//! De-obfuscation passes run ahead of regex extraction, recovering addresses hidden
+//! behind common anti-scraper tricks: HTML entity escaping, textual `at`/`dot`
+//! substitution, and Cloudflare's `data-cfemail` XOR scheme.
+
+use regex::Regex;
+use scraper::{Html, Selector};
+
+/// Decodes numeric HTML character references (`&#64;`, `&#x40;`) back to their
+/// characters. Sites often obfuscate `@` and `.` this way to dodge naive email regexes.
+fn decode_html_entities(text: &str) -> String {
+    let re = Regex::new(r"&#([xX]?[0-9a-fA-F]+);").unwrap();
+    re.replace_all(text, |caps: &regex::Captures| {
+        let reference = &caps[1];
+        let code_point = reference
+            .strip_prefix('x')
+            .or_else(|| reference.strip_prefix('X'))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .or_else(|| reference.parse::<u32>().ok());
+
+        code_point
+            .and_then(char::from_u32)
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| caps[0].to_string())
+    })
+    .into_owned()
+}
+
+/// Undoes common textual obfuscation of `@` and `.`, e.g. `"name at example dot com"`.
+/// Only worth running in aggressive mode: "at"/"dot" show up in ordinary prose too.
+fn decode_textual_substitutions(text: &str) -> String {
+    let mut result = text.to_string();
+    for at_variant in [" at ", "[at]", "(at)", " AT "] {
+        result = result.replace(at_variant, "@");
+    }
+    for dot_variant in [" dot ", "[dot]", "(dot)", " DOT "] {
+        result = result.replace(dot_variant, ".");
+    }
+    result
+}
+
+/// Decodes Cloudflare's email-protection scheme: the first hex byte is an XOR key, and
+/// every later byte, once hex-decoded, is XORed against it to recover one character.
+fn decode_cfemail(encoded: &str) -> Option<String> {
+    let bytes: Vec<u8> = (0..encoded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(encoded.get(i..i + 2)?, 16).ok())
+        .collect::<Option<_>>()?;
+    let (&key, rest) = bytes.split_first()?;
+    Some(rest.iter().map(|&b| (b ^ key) as char).collect())
+}
+
+/// Finds every `data-cfemail`-protected address in the document.
+fn extract_cfemail_addresses(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("[data-cfemail]").unwrap();
+    document
+        .select(&selector)
+        .filter_map(|el| el.value().attr("data-cfemail"))
+        .filter_map(decode_cfemail)
+        .collect()
+}
+
+/// Runs the de-obfuscation passes over `html`, returning text ready for regex email
+/// extraction. `aggressive` gates the textual `at`/`dot` substitution pass, which is the
+/// one most prone to false positives; HTML-entity and Cloudflare decoding are exact and
+/// always run.
+pub fn deobfuscate(html: &str, aggressive: bool) -> String {
+    let mut text = decode_html_entities(html);
+
+    if aggressive {
+        text = decode_textual_substitutions(&text);
+    }
+
+    for cf_email in extract_cfemail_addresses(html) {
+        text.push(' ');
+        text.push_str(&cf_email);
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_lowercase_and_uppercase_hex_entities() {
+        assert_eq!(decode_html_entities("name&#x40;example.com"), "name@example.com");
+        assert_eq!(decode_html_entities("name&#X40;example.com"), "name@example.com");
+    }
+
+    #[test]
+    fn decodes_decimal_entities() {
+        assert_eq!(decode_html_entities("name&#64;example.com"), "name@example.com");
+    }
+
+    #[test]
+    fn decodes_cfemail_using_the_leading_byte_as_the_xor_key() {
+        // Generated by XOR-ing "test@example.com" against key 0x1a.
+        assert_eq!(
+            decode_cfemail("1a6e7f696e5a7f627b776a767f34797577"),
+            Some("test@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_cfemail_hex() {
+        assert_eq!(decode_cfemail("not-hex"), None);
+    }
+
+    #[test]
+    fn aggressive_mode_undoes_textual_at_and_dot_substitution() {
+        let text = deobfuscate("name at example dot com", true);
+        assert_eq!(text, "name@example.com");
+    }
+
+    #[test]
+    fn non_aggressive_mode_leaves_textual_substitution_alone() {
+        let text = deobfuscate("name at example dot com", false);
+        assert_eq!(text, "name at example dot com");
+    }
+}
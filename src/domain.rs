@@ -0,0 +1,83 @@
+//! Registrable-domain (eTLD+1) matching backed by the Public Suffix List, so domain
+//! comparisons don't rely on naive substring checks or a hardcoded TLD allowlist.
+
+use publicsuffix::{List, Psl};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Vendored copy of the full upstream Public Suffix List
+/// (https://publicsuffix.org/list/public_suffix_list.dat); refresh by re-downloading it.
+const PUBLIC_SUFFIX_LIST: &str = include_str!("../assets/public_suffix_list.dat");
+
+fn suffix_list() -> &'static List {
+    static LIST: OnceLock<List> = OnceLock::new();
+    LIST.get_or_init(|| {
+        List::from_str(PUBLIC_SUFFIX_LIST).expect("embedded public suffix list must parse")
+    })
+}
+
+/// Returns the registrable domain (eTLD+1) of `host`, e.g. `mail.example.co.uk` -> `example.co.uk`.
+/// Returns `None` if `host` has no recognized public suffix (e.g. it's a bare IP or malformed).
+pub fn registrable_domain(host: &str) -> Option<String> {
+    suffix_list()
+        .domain(host.as_bytes())
+        .filter(|d| d.suffix().is_known())
+        .map(|d| String::from_utf8_lossy(d.as_bytes()).into_owned())
+}
+
+/// Whether `tld` is a suffix recognized by the Public Suffix List, replacing the old
+/// hardcoded `COMMON_TLDS` allowlist.
+pub fn is_known_tld(tld: &str) -> bool {
+    suffix_list()
+        .suffix(tld.as_bytes())
+        .is_some_and(|s| s.is_known())
+}
+
+/// Whether `email`'s domain belongs to `site_host`. When `match_subdomain` is set this
+/// requires an exact host match; otherwise it only requires the registrable domains
+/// (eTLD+1) to agree, so `foo@mail.example.com` matches a crawl of `example.com` but
+/// `foo@notexample.com` no longer does.
+pub fn email_matches_site(email: &str, site_host: &str, match_subdomain: bool) -> bool {
+    let Some(email_host) = email.rsplit('@').next() else {
+        return false;
+    };
+
+    if match_subdomain {
+        return email_host.eq_ignore_ascii_case(site_host);
+    }
+
+    match (registrable_domain(email_host), registrable_domain(site_host)) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(&b),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registrable_domain_strips_subdomains() {
+        assert_eq!(
+            registrable_domain("mail.example.co.uk"),
+            Some("example.co.uk".to_string())
+        );
+        assert_eq!(registrable_domain("example.com"), Some("example.com".to_string()));
+    }
+
+    #[test]
+    fn email_matches_site_accepts_subdomain_by_default() {
+        assert!(email_matches_site("foo@mail.example.com", "example.com", false));
+    }
+
+    #[test]
+    fn email_matches_site_rejects_different_domain_sharing_a_suffix() {
+        assert!(!email_matches_site("foo@notexample.com", "example.com", false));
+    }
+
+    #[test]
+    fn email_matches_site_requires_exact_host_when_subdomain_matching() {
+        assert!(!email_matches_site("foo@mail.example.com", "example.com", true));
+        assert!(email_matches_site("foo@example.com", "example.com", true));
+    }
+}
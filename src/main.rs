@@ -1,3 +1,10 @@
+mod deobfuscate;
+mod domain;
+mod output;
+mod politeness;
+mod settings;
+mod verify;
+
 use clap::{Arg, ArgAction, Command};
 use colored::*;
 use regex::Regex;
@@ -8,13 +15,6 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
-// List of common TLDs for validation
-const COMMON_TLDS: &[&str] = &[
-    "com", "org", "net", "edu", "gov", "mil", "int", "co", "io", "me", "biz",
-    "info", "us", "uk", "ca", "de", "jp", "fr", "au", "ru", "ch", "it", "nl",
-    "se", "no", "es", "mil", "gov", "edu", "tv", "ly",
-];
-
 fn main() {
     // Parse command-line arguments
     let matches = Command::new("emails")
@@ -23,10 +23,16 @@ fn main() {
         .about("Searches a website for email addresses.")
         .arg(
             Arg::new("URL")
-                .help("The URL to scrape")
-                .required(true)
+                .help("The URL to scrape (optional if --config provides seed URLs)")
                 .index(1),
         )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .help("TOML config file with seed URLs, scope, headers, and option overrides")
+                .value_name("FILE")
+                .action(ArgAction::Set),
+        )
         .arg(
             Arg::new("depth")
                 .short('d')
@@ -56,57 +62,145 @@ fn main() {
         .arg(
             Arg::new("strict")
                 .long("strict")
-                .help("Only print emails that match the domain provided")
+                .help("Only print emails whose registrable domain matches the site's")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("match-subdomain")
+                .long("match-subdomain")
+                .help("In strict mode, require the exact host to match instead of just the registrable domain")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verify")
+                .long("verify")
+                .help("After collection, probe each address's mail server to check deliverability")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("rate")
+                .long("rate")
+                .help("Max requests per second to send to any single host")
+                .value_name("RPS")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("respect-robots")
+                .long("respect-robots")
+                .help("Fetch and honor each host's robots.txt (Disallow rules and Crawl-delay)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .help("Write the collected emails, with source-URL provenance, to this file")
+                .value_name("FILE")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Format for --output")
+                .value_name("json|csv")
+                .default_value("json")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("aggressive-decode")
+                .long("aggressive-decode")
+                .help("Also undo textual obfuscation like \"name at example dot com\" (can false-positive)")
                 .action(ArgAction::SetTrue),
         )
         .get_matches();
 
-    // Get command-line argument values
-    let input_url = matches.get_one::<String>("URL").unwrap();
-    let depth = matches
-        .get_one::<String>("depth")
-        .unwrap()
-        .parse::<usize>()
-        .expect("Depth must be a number");
-    let num_threads = matches
-        .get_one::<String>("threads")
-        .unwrap()
-        .parse::<usize>()
-        .expect("Threads must be a number");
-    let timeout = matches
-        .get_one::<String>("timeout")
-        .unwrap()
-        .parse::<u64>()
-        .expect("Timeout must be a number");
-    let strict_mode = matches.get_flag("strict");
+    // Load the config file, if any; CLI flags passed explicitly still win over it.
+    let config = matches.get_one::<String>("config").map(|path| {
+        settings::Settings::load(path).unwrap_or_else(|err| {
+            eprintln!("Failed to load config {}: {}", path, err);
+            std::process::exit(1);
+        })
+    });
 
-    // Prepend http:// if missing
-    let url = if input_url.starts_with("http://") || input_url.starts_with("https://") {
-        input_url.to_string()
+    // Get command-line argument values, falling back to the config file where the CLI
+    // flag was left at its default.
+    let depth = resolve_override(&matches, "depth", config.as_ref().and_then(|c| c.depth));
+    let num_threads = resolve_override(&matches, "threads", config.as_ref().and_then(|c| c.threads));
+    let timeout = resolve_override(&matches, "timeout", config.as_ref().and_then(|c| c.timeout));
+    let strict_mode = matches.get_flag("strict");
+    let match_subdomain = matches.get_flag("match-subdomain");
+    let verify_mode = matches.get_flag("verify");
+    let rate_limit = matches.get_one::<String>("rate").map(|r| {
+        let rate = r.parse::<f64>().expect("Rate must be a number");
+        assert!(rate > 0.0, "Rate must be a positive number");
+        rate
+    });
+    let respect_robots = matches.get_flag("respect-robots");
+    let output_path = matches.get_one::<String>("output").cloned();
+    let output_format = matches
+        .get_one::<String>("format")
+        .unwrap()
+        .parse::<output::OutputFormat>()
+        .expect("Invalid --format");
+    let aggressive_decode = matches.get_flag("aggressive-decode");
+
+    // A URL given on the command line always wins; otherwise fall back to the config
+    // file's seed list.
+    let seed_urls: Vec<String> = if let Some(input_url) = matches.get_one::<String>("URL") {
+        vec![normalize_url(input_url)]
+    } else if let Some(cfg) = config.as_ref().filter(|c| !c.seeds.is_empty()) {
+        cfg.seeds.iter().map(|s| normalize_url(s)).collect()
     } else {
-        format!("http://{}", input_url)
+        eprintln!("A URL is required, either on the command line or as `seeds` in --config");
+        std::process::exit(1);
     };
 
-    println!("Starting email scraping on: {}", url);
+    println!("Starting email scraping on: {}", seed_urls.join(", "));
 
     // Shared data structures
-    let emails_found = Arc::new(Mutex::new(HashSet::new()));
+    let emails_found: Arc<Mutex<output::EmailRecords>> = Arc::new(Mutex::new(output::EmailRecords::new()));
     let urls_to_visit = Arc::new(Mutex::new(VecDeque::new()));
     let visited_urls = Arc::new(Mutex::new(HashSet::new()));
-    urls_to_visit
-        .lock()
-        .unwrap()
-        .push_back((url.clone(), 0));
-    visited_urls.lock().unwrap().insert(url.clone());
-
-    // HTTP client
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .unwrap();
+    for seed in &seed_urls {
+        urls_to_visit.lock().unwrap().push_back((seed.clone(), 0));
+        visited_urls.lock().unwrap().insert(seed.clone());
+    }
 
-    // Domain extraction for email matching
-    let domain = get_domain(&url);
+    // HTTP client, with an optional custom user-agent and headers from the config file.
+    let mut client_builder = Client::builder().timeout(Duration::from_secs(10));
+    if let Some(config) = &config {
+        if let Some(user_agent) = &config.user_agent {
+            client_builder = client_builder.user_agent(user_agent);
+        }
+        if !config.headers.is_empty() {
+            let mut header_map = reqwest::header::HeaderMap::new();
+            for (name, value) in &config.headers {
+                if let (Ok(name), Ok(value)) = (
+                    reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+                    reqwest::header::HeaderValue::from_str(value),
+                ) {
+                    header_map.insert(name, value);
+                }
+            }
+            client_builder = client_builder.default_headers(header_map);
+        }
+    }
+    let client = client_builder.build().unwrap();
+
+    // Domain extraction for email matching; with multiple seeds the first is treated as
+    // the primary site.
+    let domain = get_domain(&seed_urls[0]);
+    let scope = Arc::new(config.as_ref().map(|c| c.scope.clone()).unwrap_or_default());
+
+    // Politeness controls: a rate limiter is needed whenever a rate was requested, or
+    // whenever robots.txt might supply a Crawl-delay to enforce.
+    let rate_limiter = if rate_limit.is_some() || respect_robots {
+        Some(Arc::new(politeness::RateLimiter::new(
+            rate_limit.unwrap_or(f64::INFINITY),
+        )))
+    } else {
+        None
+    };
+    let robots_cache = respect_robots.then(|| Arc::new(politeness::RobotsCache::new(client.clone())));
 
     let start_time = Instant::now();
     let mut handles = vec![];
@@ -120,6 +214,11 @@ fn main() {
         let domain = domain.clone();
         let start_time = start_time.clone();
         let strict_mode = strict_mode;
+        let match_subdomain = match_subdomain;
+        let rate_limiter = rate_limiter.clone();
+        let robots_cache = robots_cache.clone();
+        let aggressive_decode = aggressive_decode;
+        let scope = Arc::clone(&scope);
 
         let handle = thread::spawn(move || loop {
             // Check for timeout
@@ -140,13 +239,24 @@ fn main() {
                 continue;
             }
 
+            let current_host = get_domain(&current_url);
+            if let Some(robots) = &robots_cache {
+                if let (Some(limiter), Some(delay)) = (&rate_limiter, robots.crawl_delay(&current_url)) {
+                    limiter.set_min_interval(&current_host, delay);
+                }
+            }
+            if let Some(limiter) = &rate_limiter {
+                limiter.wait(&current_host);
+            }
+
             // Fetch the page content
             match client.get(&current_url).send() {
                 Ok(resp) => {
                     if let Ok(text) = resp.text() {
                         // Extract emails
-                        let emails = extract_emails(&text);
-                        let mut emails_set = emails_found.lock().unwrap();
+                        let decoded_text = deobfuscate::deobfuscate(&text, aggressive_decode);
+                        let emails = extract_emails(&decoded_text);
+                        let mut emails_map = emails_found.lock().unwrap();
                         for email in emails {
                             let email = email.trim().trim_start_matches(|c| !char::is_alphanumeric(c));
                             let email_lower = email.to_lowercase();
@@ -174,11 +284,21 @@ fn main() {
                                 continue;
                             }
 
-                            if emails_set.insert(final_email.clone()) {
-                                let email_matches_domain = final_email
-                                    .to_lowercase()
-                                    .ends_with(&domain.to_lowercase());
-
+                            let email_matches_domain = domain::email_matches_site(
+                                &final_email,
+                                &domain,
+                                match_subdomain,
+                            );
+
+                            let is_new = output::record_email(
+                                &mut emails_map,
+                                &final_email,
+                                &current_url,
+                                email_matches_domain,
+                                should_decode,
+                            );
+
+                            if is_new {
                                 if strict_mode {
                                     if email_matches_domain {
                                         println!("{}", final_email.green());
@@ -195,7 +315,7 @@ fn main() {
 
                         // Extract links and add to queue
                         if current_depth < depth {
-                            let links = extract_links(&text, &current_url);
+                            let links = extract_links(&text, &current_url, robots_cache.as_deref(), &scope);
                             let mut urls = urls_to_visit.lock().unwrap();
                             let mut visited = visited_urls.lock().unwrap();
                             for link in links {
@@ -225,6 +345,61 @@ fn main() {
         "Finished scraping. Found {} emails.",
         emails_found.lock().unwrap().len()
     );
+
+    if verify_mode {
+        verify_emails(&emails_found, num_threads);
+    }
+
+    if let Some(output_path) = output_path {
+        let records = emails_found.lock().unwrap();
+        match output::write_records(&records, &output_path, output_format) {
+            Ok(()) => println!("Wrote {} records to {}", records.len(), output_path),
+            Err(err) => eprintln!("Failed to write {}: {}", output_path, err),
+        }
+    }
+}
+
+// Probes each collected address's mail server for deliverability and prints a status tag.
+fn verify_emails(emails_found: &Arc<Mutex<output::EmailRecords>>, num_threads: usize) {
+    println!("\nVerifying deliverability...");
+
+    let our_host = hostname::get()
+        .ok()
+        .and_then(|h| h.into_string().ok())
+        .unwrap_or_else(|| "localhost".to_string());
+    let verifier = Arc::new(verify::Verifier::new(our_host));
+
+    let mut pending: Vec<String> = emails_found.lock().unwrap().keys().cloned().collect();
+    pending.sort();
+    let queue = Arc::new(Mutex::new(VecDeque::from(pending)));
+
+    let mut handles = vec![];
+    for _ in 0..num_threads {
+        let queue = Arc::clone(&queue);
+        let verifier = Arc::clone(&verifier);
+        handles.push(thread::spawn(move || loop {
+            let email = {
+                let mut queue = queue.lock().unwrap();
+                match queue.pop_front() {
+                    Some(email) => email,
+                    None => break,
+                }
+            };
+
+            let status = verifier.verify(&email);
+            let tag = format!("[{}]", status.label());
+            let tag = match status {
+                verify::VerifyStatus::Deliverable => tag.green(),
+                verify::VerifyStatus::Rejected => tag.red(),
+                verify::VerifyStatus::Unverifiable | verify::VerifyStatus::Unknown => tag.yellow(),
+            };
+            println!("{email} {tag}");
+        }));
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
 }
 
 // Function to extract emails using regex
@@ -242,9 +417,9 @@ fn extract_emails(text: &str) -> Vec<String> {
 fn is_likely_rot13_encoded(email: &str) -> bool {
     // Simple heuristic: check if the domain ends with a known TLD after decoding
     let decoded_email = rot13_decode(email);
-    let domain = decoded_email.split('@').nth(1).unwrap_or("");
-    let tld = domain.split('.').last().unwrap_or("");
-    COMMON_TLDS.contains(&tld)
+    let host = decoded_email.split('@').nth(1).unwrap_or("");
+    let tld = host.split('.').last().unwrap_or("");
+    domain::is_known_tld(tld)
 }
 
 // Function to decode ROT13 encoded emails
@@ -276,15 +451,20 @@ fn is_valid_email(email: &str) -> bool {
     .unwrap();
 
     if let Some(caps) = email_regex.captures(email) {
-        let tld = &caps[1].to_lowercase();
-        COMMON_TLDS.contains(&tld.as_str())
+        let tld = caps[1].to_lowercase();
+        domain::is_known_tld(&tld)
     } else {
         false
     }
 }
 
 // Function to extract links from the HTML content
-fn extract_links(html: &str, base_url: &str) -> Vec<String> {
+fn extract_links(
+    html: &str,
+    base_url: &str,
+    robots: Option<&politeness::RobotsCache>,
+    scope: &settings::Scope,
+) -> Vec<String> {
     let document = Html::parse_document(html);
     let selector = Selector::parse("a[href]").unwrap();
     let mut links = Vec::new();
@@ -320,6 +500,16 @@ fn extract_links(html: &str, base_url: &str) -> Vec<String> {
                 },
             };
 
+            if let Some(robots) = robots {
+                if !robots.is_allowed(&full_url) {
+                    continue;
+                }
+            }
+
+            if !scope.allows(&full_url) {
+                continue;
+            }
+
             links.push(full_url);
         }
     }
@@ -340,3 +530,31 @@ fn get_domain(url: &str) -> String {
         Err(_) => "".to_string(),
     }
 }
+
+// Prepends http:// to a bare host/URL so both "example.com" and full URLs work as seeds.
+fn normalize_url(input: &str) -> String {
+    if input.starts_with("http://") || input.starts_with("https://") {
+        input.to_string()
+    } else {
+        format!("http://{}", input)
+    }
+}
+
+// Resolves one numeric option: an explicitly-passed CLI flag always wins, otherwise the
+// config file's value is used, falling back to the flag's default.
+fn resolve_override<T>(matches: &clap::ArgMatches, id: &str, file_value: Option<T>) -> T
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Debug,
+{
+    let cli_value = matches
+        .get_one::<String>(id)
+        .unwrap()
+        .parse::<T>()
+        .unwrap_or_else(|e| panic!("Invalid value for --{id}: {e:?}"));
+
+    match matches.value_source(id) {
+        Some(clap::parser::ValueSource::CommandLine) => cli_value,
+        _ => file_value.unwrap_or(cli_value),
+    }
+}
@@ -0,0 +1,97 @@
+//! Structured result records (with source-page provenance) and their JSON/CSV
+//! serialization, so the harvested set can feed downstream pipelines instead of being
+//! scraped back out of colored terminal output.
+
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::str::FromStr;
+
+/// Everything we know about one harvested address.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailRecord {
+    pub email: String,
+    pub source_urls: BTreeSet<String>,
+    pub matches_domain: bool,
+    pub rot13_decoded: bool,
+}
+
+/// All harvested addresses, keyed by the (lowercased, decoded) email itself.
+pub type EmailRecords = HashMap<String, EmailRecord>;
+
+/// Records that `email` was found on `source_url`, creating its entry on first sight and
+/// just adding the source page on repeat sightings. Returns whether this is the first
+/// time `email` has been seen, so callers can print a one-line summary as they go.
+pub fn record_email(
+    records: &mut EmailRecords,
+    email: &str,
+    source_url: &str,
+    matches_domain: bool,
+    rot13_decoded: bool,
+) -> bool {
+    let is_new = !records.contains_key(email);
+    let record = records.entry(email.to_string()).or_insert_with(|| EmailRecord {
+        email: email.to_string(),
+        source_urls: BTreeSet::new(),
+        matches_domain,
+        rot13_decoded,
+    });
+    record.source_urls.insert(source_url.to_string());
+    is_new
+}
+
+/// Output file format for `--output`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(format!("Unsupported output format: {other} (expected json or csv)")),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    email: &'a str,
+    source_urls: String,
+    matches_domain: bool,
+    rot13_decoded: bool,
+}
+
+/// Writes `records` to `path` in `format`, sorted by email for stable, diffable output.
+pub fn write_records(records: &EmailRecords, path: &str, format: OutputFormat) -> io::Result<()> {
+    let mut sorted: Vec<&EmailRecord> = records.values().collect();
+    sorted.sort_by(|a, b| a.email.cmp(&b.email));
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&sorted)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            std::fs::write(path, json)
+        }
+        OutputFormat::Csv => {
+            let mut writer =
+                csv::Writer::from_path(path).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            for record in sorted {
+                writer
+                    .serialize(CsvRow {
+                        email: &record.email,
+                        source_urls: record.source_urls.iter().cloned().collect::<Vec<_>>().join(";"),
+                        matches_domain: record.matches_domain,
+                        rot13_decoded: record.rot13_decoded,
+                    })
+                    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            }
+            writer.flush()
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Crawl politeness controls: a per-host token-bucket rate limiter and robots.txt
+//! compliance, both cached per host so every worker thread shares the same state.
+
+use reqwest::blocking::Client;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Upper bound on a `Crawl-delay` we'll honor, so a site can't stall a worker thread
+/// indefinitely just by advertising an enormous delay.
+const MAX_CRAWL_DELAY_SECS: f64 = 300.0;
+
+/// Per-host token bucket. Workers call `wait` before every request; it blocks until a
+/// token is available rather than dropping or rejecting the request.
+pub struct RateLimiter {
+    requests_per_second: f64,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+    /// Set from a host's robots.txt `Crawl-delay`; limits the rate to at most one request
+    /// per this interval, on top of `requests_per_second`.
+    min_interval: Option<Duration>,
+}
+
+impl TokenBucket {
+    fn full() -> Self {
+        Self {
+            tokens: 1.0,
+            last_refill: Instant::now(),
+            min_interval: None,
+        }
+    }
+
+    fn refill(&mut self, rate_per_sec: f64) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * rate_per_sec).min(1.0);
+    }
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64) -> Self {
+        Self {
+            requests_per_second,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Blocks the calling thread until a token for `host` is available, consuming it.
+    pub fn wait(&self, host: &str) {
+        loop {
+            let sleep_for = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let bucket = buckets.entry(host.to_string()).or_insert_with(TokenBucket::full);
+                let rate = bucket
+                    .min_interval
+                    .map(|d| (1.0 / d.as_secs_f64()).min(self.requests_per_second))
+                    .unwrap_or(self.requests_per_second);
+                bucket.refill(rate);
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    None
+                } else {
+                    Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+                }
+            };
+
+            match sleep_for {
+                Some(duration) => thread::sleep(duration),
+                None => return,
+            }
+        }
+    }
+
+    /// Applies a `Crawl-delay` learned from `host`'s robots.txt as a floor on its interval.
+    pub fn set_min_interval(&self, host: &str, min_interval: Duration) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(host.to_string()).or_insert_with(TokenBucket::full);
+        bucket.min_interval = Some(min_interval);
+    }
+}
+
+#[derive(Clone, Default)]
+struct RobotsRules {
+    disallow: Vec<String>,
+    crawl_delay: Option<Duration>,
+}
+
+/// Fetches and parses each host's robots.txt once, caching the result for every
+/// subsequent lookup against that host.
+pub struct RobotsCache {
+    client: Client,
+    cache: Mutex<HashMap<String, RobotsRules>>,
+}
+
+impl RobotsCache {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether our user agent is permitted to fetch `url` per its host's robots.txt.
+    pub fn is_allowed(&self, url: &str) -> bool {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return true;
+        };
+        let Some(host) = parsed.host_str() else {
+            return true;
+        };
+
+        let rules = self.rules_for(parsed.scheme(), host);
+        let path = parsed.path();
+        !rules.disallow.iter().any(|prefix| path.starts_with(prefix.as_str()))
+    }
+
+    /// The `Crawl-delay` declared by `url`'s host, if any.
+    pub fn crawl_delay(&self, url: &str) -> Option<Duration> {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        self.rules_for(parsed.scheme(), host).crawl_delay
+    }
+
+    fn rules_for(&self, scheme: &str, host: &str) -> RobotsRules {
+        if let Some(rules) = self.cache.lock().unwrap().get(host) {
+            return rules.clone();
+        }
+
+        let robots_url = format!("{scheme}://{host}/robots.txt");
+        let rules = self
+            .client
+            .get(&robots_url)
+            .send()
+            .ok()
+            .and_then(|resp| resp.text().ok())
+            .map(|body| parse_robots_txt(&body))
+            .unwrap_or_default();
+
+        self.cache.lock().unwrap().insert(host.to_string(), rules.clone());
+        rules
+    }
+}
+
+/// Parses the `User-agent: *` group of a robots.txt body; we don't identify as a named
+/// bot, so we only honor rules that apply to every crawler.
+fn parse_robots_txt(body: &str) -> RobotsRules {
+    let mut rules = RobotsRules::default();
+    let mut applies = false;
+
+    for raw_line in body.lines() {
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match field.trim().to_lowercase().as_str() {
+            "user-agent" => applies = value == "*",
+            "disallow" if applies && !value.is_empty() => rules.disallow.push(value.to_string()),
+            "crawl-delay" if applies => {
+                if let Ok(secs) = value.parse::<f64>() {
+                    // robots.txt is attacker-controlled input: `Duration::from_secs_f64`
+                    // panics on negative/NaN/infinite values, so reject those and clamp
+                    // anything absurdly large instead of trusting the site's number.
+                    if secs.is_finite() && secs >= 0.0 {
+                        rules.crawl_delay = Some(Duration::from_secs_f64(secs.min(MAX_CRAWL_DELAY_SECS)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_disallow_and_crawl_delay_for_the_wildcard_agent() {
+        let rules = parse_robots_txt(
+            "User-agent: *\nDisallow: /private\nDisallow: /admin\nCrawl-delay: 2.5\n",
+        );
+        assert_eq!(rules.disallow, vec!["/private", "/admin"]);
+        assert_eq!(rules.crawl_delay, Some(Duration::from_secs_f64(2.5)));
+    }
+
+    #[test]
+    fn ignores_rules_scoped_to_a_named_agent() {
+        let rules = parse_robots_txt("User-agent: Googlebot\nDisallow: /private\n");
+        assert!(rules.disallow.is_empty());
+    }
+
+    #[test]
+    fn rejects_a_negative_crawl_delay_instead_of_panicking() {
+        let rules = parse_robots_txt("User-agent: *\nCrawl-delay: -1\n");
+        assert_eq!(rules.crawl_delay, None);
+    }
+
+    #[test]
+    fn rejects_a_non_finite_crawl_delay() {
+        let rules = parse_robots_txt("User-agent: *\nCrawl-delay: 1e400\n");
+        assert_eq!(rules.crawl_delay, None);
+    }
+
+    #[test]
+    fn clamps_an_absurdly_large_crawl_delay() {
+        let rules = parse_robots_txt("User-agent: *\nCrawl-delay: 999999\n");
+        assert_eq!(rules.crawl_delay, Some(Duration::from_secs_f64(MAX_CRAWL_DELAY_SECS)));
+    }
+}
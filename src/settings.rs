@@ -0,0 +1,76 @@
+//! TOML config file support, so recurring crawl jobs can be described once instead of
+//! re-typed as a long command line. Settings loaded here are overridden by any
+//! corresponding CLI flag the user passes explicitly.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, Default)]
+pub struct Settings {
+    /// Seed URLs to start crawling from, used when no URL is given on the command line.
+    #[serde(default)]
+    pub seeds: Vec<String>,
+    #[serde(default)]
+    pub depth: Option<usize>,
+    #[serde(default)]
+    pub threads: Option<usize>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// Overrides the HTTP client's User-Agent header.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Extra request headers sent with every HTTP request.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub scope: Scope,
+}
+
+/// Bounds on what `extract_links` is allowed to enqueue. An empty allow list means "no
+/// restriction"; deny lists always take priority over allow lists.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct Scope {
+    #[serde(default)]
+    pub allow_hosts: Vec<String>,
+    #[serde(default)]
+    pub deny_hosts: Vec<String>,
+    #[serde(default)]
+    pub allow_paths: Vec<String>,
+    #[serde(default)]
+    pub deny_paths: Vec<String>,
+}
+
+impl Settings {
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        toml::from_str(&text).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl Scope {
+    /// Whether `url` falls within the configured crawl scope.
+    pub fn allows(&self, url: &str) -> bool {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return true;
+        };
+        let Some(host) = parsed.host_str() else {
+            return true;
+        };
+        let path = parsed.path();
+
+        if self.deny_hosts.iter().any(|h| h == host) {
+            return false;
+        }
+        if self.deny_paths.iter().any(|p| path.starts_with(p.as_str())) {
+            return false;
+        }
+        if !self.allow_hosts.is_empty() && !self.allow_hosts.iter().any(|h| h == host) {
+            return false;
+        }
+        if !self.allow_paths.is_empty() && !self.allow_paths.iter().any(|p| path.starts_with(p.as_str())) {
+            return false;
+        }
+
+        true
+    }
+}
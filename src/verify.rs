@@ -0,0 +1,232 @@
+//! SMTP-level deliverability verification: checks whether a harvested address is
+//! actually accepted by its domain's mail server without ever sending `DATA`.
+
+use hickory_resolver::config::{ResolverConfig, ResolverOpts};
+use hickory_resolver::Resolver;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of probing a single address against its mail server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyStatus {
+    /// RCPT TO was accepted (250/251).
+    Deliverable,
+    /// RCPT TO was rejected (550/551/553).
+    Rejected,
+    /// The domain accepts any recipient, so acceptance says nothing about this address.
+    Unverifiable,
+    /// DNS lookup, connection, or SMTP dialogue failed or returned an unexpected code.
+    Unknown,
+}
+
+impl VerifyStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            VerifyStatus::Deliverable => "deliverable",
+            VerifyStatus::Rejected => "rejected",
+            VerifyStatus::Unverifiable => "unverifiable",
+            VerifyStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// A live, post-EHLO connection to one domain's MX host. Kept open and reused across
+/// probes instead of reconnecting for every recipient; each probe just RSETs the
+/// transaction before its own MAIL FROM/RCPT TO.
+struct Session {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // Best-effort: we're closing either way, so ignore write errors here.
+        let _ = self.writer.write_all(b"QUIT\r\n");
+    }
+}
+
+/// Per-domain state: the MX host and catch-all verdict (resolved once) plus a reusable
+/// SMTP session, all behind one lock so every probe against a domain is serialized onto
+/// the same connection regardless of which worker thread it comes from.
+#[derive(Default)]
+struct DomainState {
+    mx_host: Option<String>,
+    resolved: bool,
+    catch_all: bool,
+    session: Option<Session>,
+}
+
+/// Verifies addresses via MX lookup + a bare SMTP handshake, keeping one reusable
+/// connection per domain so many recipients at the same domain share a single
+/// TCP/EHLO session instead of reconnecting for each address.
+pub struct Verifier {
+    our_host: String,
+    domains: Mutex<HashMap<String, Arc<Mutex<DomainState>>>>,
+}
+
+impl Verifier {
+    pub fn new(our_host: String) -> Self {
+        Self {
+            our_host,
+            domains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn domain_state(&self, domain: &str) -> Arc<Mutex<DomainState>> {
+        Arc::clone(
+            self.domains
+                .lock()
+                .unwrap()
+                .entry(domain.to_string())
+                .or_insert_with(|| Arc::new(Mutex::new(DomainState::default()))),
+        )
+    }
+
+    /// Probes `email` for deliverability, resolving its domain's MX/catch-all status and
+    /// opening a session on first contact, then reusing that session for every later
+    /// address at the same domain.
+    pub fn verify(&self, email: &str) -> VerifyStatus {
+        let Some(target_domain) = email.rsplit('@').next() else {
+            return VerifyStatus::Unknown;
+        };
+
+        let state_lock = self.domain_state(target_domain);
+        let mut state = state_lock.lock().unwrap();
+
+        if !state.resolved {
+            state.resolved = true;
+            state.mx_host = resolve_mx(target_domain);
+            if let Some(mx_host) = state.mx_host.clone() {
+                state.session = open_session(&mx_host, &self.our_host).ok();
+            }
+            if state.session.is_some() {
+                let probe_address = format!("{}@{target_domain}", random_local_part());
+                state.catch_all =
+                    self.probe(&mut state, &probe_address) == Ok(VerifyStatus::Deliverable);
+            }
+        }
+
+        if state.session.is_none() {
+            return VerifyStatus::Unknown;
+        }
+        if state.catch_all {
+            return VerifyStatus::Unverifiable;
+        }
+
+        self.probe(&mut state, email).unwrap_or(VerifyStatus::Unknown)
+    }
+
+    /// Runs one RSET/MAIL FROM/RCPT TO probe over `state`'s session, reopening it once if
+    /// the reused connection turned out to be dead.
+    fn probe(&self, state: &mut DomainState, recipient: &str) -> std::io::Result<VerifyStatus> {
+        let Some(session) = state.session.as_mut() else {
+            return Ok(VerifyStatus::Unknown);
+        };
+
+        match probe_over_session(session, &self.our_host, recipient) {
+            Ok(status) => Ok(status),
+            Err(_) => {
+                // The reused connection may have been closed by the server; reconnect
+                // once and retry before giving up on this recipient.
+                let mx_host = state.mx_host.clone();
+                state.session = mx_host.and_then(|mx| open_session(&mx, &self.our_host).ok());
+                match state.session.as_mut() {
+                    Some(session) => probe_over_session(session, &self.our_host, recipient),
+                    None => Ok(VerifyStatus::Unknown),
+                }
+            }
+        }
+    }
+}
+
+fn resolve_mx(domain: &str) -> Option<String> {
+    let resolver = Resolver::new(ResolverConfig::default(), ResolverOpts::default()).ok()?;
+    let mut records: Vec<_> = resolver.mx_lookup(domain).ok()?.iter().cloned().collect();
+    records.sort_by_key(|mx| mx.preference());
+    records
+        .into_iter()
+        .next()
+        .map(|mx| mx.exchange().to_utf8().trim_end_matches('.').to_string())
+}
+
+/// A local part that should not exist at the probed domain, used to detect catch-all servers.
+fn random_local_part() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos();
+    format!("emails-probe-{nanos:x}")
+}
+
+/// Connects to `mx_host` and completes the greeting + EHLO, leaving the session ready for
+/// repeated MAIL FROM/RCPT TO probes.
+fn open_session(mx_host: &str, our_host: &str) -> std::io::Result<Session> {
+    let addr = (mx_host, 25)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no address for MX host"))?;
+    let stream = TcpStream::connect_timeout(&addr, SMTP_TIMEOUT)?;
+    stream.set_read_timeout(Some(SMTP_TIMEOUT))?;
+    stream.set_write_timeout(Some(SMTP_TIMEOUT))?;
+
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    read_reply(&mut reader)?; // server greeting
+    send_command(&mut writer, &mut reader, &format!("EHLO {our_host}"))?;
+
+    Ok(Session { writer, reader })
+}
+
+/// One RCPT TO probe over an already-open session: RSET clears any prior transaction so
+/// the connection can be reused for the next recipient.
+fn probe_over_session(
+    session: &mut Session,
+    our_host: &str,
+    recipient: &str,
+) -> std::io::Result<VerifyStatus> {
+    send_command(&mut session.writer, &mut session.reader, "RSET")?;
+    send_command(
+        &mut session.writer,
+        &mut session.reader,
+        &format!("MAIL FROM:<probe@{our_host}>"),
+    )?;
+    let (code, _) = send_command(
+        &mut session.writer,
+        &mut session.reader,
+        &format!("RCPT TO:<{recipient}>"),
+    )?;
+
+    Ok(match code {
+        250 | 251 => VerifyStatus::Deliverable,
+        550 | 551 | 553 => VerifyStatus::Rejected,
+        _ => VerifyStatus::Unknown,
+    })
+}
+
+fn send_command(
+    writer: &mut TcpStream,
+    reader: &mut BufReader<TcpStream>,
+    command: &str,
+) -> std::io::Result<(u16, String)> {
+    writer.write_all(format!("{command}\r\n").as_bytes())?;
+    read_reply(reader)
+}
+
+/// Reads one full SMTP reply, which may span several lines (`"250-..."` continuations
+/// followed by a final `"250 ..."` line), and returns the code and the last line.
+fn read_reply(reader: &mut BufReader<TcpStream>) -> std::io::Result<(u16, String)> {
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let code = line.get(0..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue; // more continuation lines to come
+        }
+        return Ok((code, line));
+    }
+}